@@ -21,6 +21,7 @@
 
 use std::ffi::CString;
 use std::mem;
+use std::sync::Mutex;
 
 mod c {
     extern "C" {
@@ -34,6 +35,14 @@ mod c {
             format: *const libc::c_char,
             tm: *const libc::tm,
         ) -> usize;
+        #[cfg(unix)]
+        pub(crate) fn strftime_l(
+            s: *mut libc::c_char,
+            max: libc::size_t,
+            format: *const libc::c_char,
+            tm: *const libc::tm,
+            loc: libc::locale_t,
+        ) -> usize;
         pub(crate) fn time(tloc: *const libc::time_t) -> libc::time_t;
         #[cfg(unix)]
         pub(crate) fn localtime_r(t: *const libc::time_t, tm: *mut libc::tm);
@@ -43,6 +52,148 @@ mod c {
         pub(crate) fn gmtime_r(t: *const libc::time_t, tm: *mut libc::tm);
         #[cfg(windows)]
         pub(crate) fn _gmtime64_s(tm: *mut libc::tm, t: *const libc::time_t);
+        #[cfg(unix)]
+        pub(crate) fn strptime(
+            s: *const libc::c_char,
+            format: *const libc::c_char,
+            tm: *mut libc::tm,
+        ) -> *const libc::c_char;
+        pub(crate) fn mktime(tm: *mut libc::tm) -> libc::time_t;
+        #[cfg(unix)]
+        pub(crate) fn timegm(tm: *mut libc::tm) -> libc::time_t;
+        #[cfg(unix)]
+        pub(crate) fn clock_gettime(clock_id: libc::clockid_t, tp: *mut libc::timespec) -> libc::c_int;
+    }
+}
+
+/// A named timezone used with [`strftime_in_zone`].
+///
+/// **Not a reentrant handle.** [`strftime_in_zone`] still mutates the
+/// global `TZ` environment variable for the duration of each call; calls
+/// made through this crate's own API ([`strftime_in_zone`],
+/// [`convert_epoch_between_zones`]) are serialized against each other with
+/// an internal lock (see [`TZ_LOCK`]), but that offers no protection
+/// against other code in the process that reads or writes `TZ` directly.
+///
+/// An earlier draft of this type was built on the IANA/NetBSD tzcode
+/// reentrant-timezone API (`tzalloc`/`tzfree`/`localtime_rz`), but those
+/// symbols don't actually exist in glibc (`nm -D libc.so.6` shows nothing
+/// for them; that API is tzcode/BSD-only) -- and this crate wraps glibc's
+/// `strftime`. So `TimeZone` just stores the zone name, and
+/// `strftime_in_zone` falls back to the `TZ` + `tzset()` + `localtime_r()`
+/// dance also used by `convert_epoch_between_zones`.
+pub struct TimeZone(String);
+
+impl TimeZone {
+    /// Name a timezone, e.g. `"Europe/Brussels"` or `"UTC"`.
+    pub fn new(name: &str) -> TimeZone {
+        TimeZone(name.to_string())
+    }
+}
+
+/// Serializes the `TZ` read-mutate-restore critical sections in
+/// [`strftime_in_zone`] and [`convert_epoch_between_zones`] against each
+/// other, so at least those two can't race themselves. It does nothing
+/// for other code in the process that reads or writes `TZ` directly.
+static TZ_LOCK: Mutex<()> = Mutex::new(());
+
+/// A locale object for use with the `_l` family of functions, such as
+/// [`strftime_local_l`] and [`strftime_gmt_l`].
+///
+/// Unlike [`set_locale`], which calls `setlocale()` and mutates process-wide
+/// state, a `Locale` is constructed with `newlocale()` and passed explicitly
+/// to each call, so different threads can format dates in different
+/// locales at the same time without stepping on each other.
+#[cfg(unix)]
+pub struct Locale(libc::locale_t);
+
+#[cfg(unix)]
+impl Locale {
+    /// Create a new locale from its name, e.g. `"fr_BE.UTF-8"`.
+    ///
+    /// Panics if the underlying `newlocale()` call fails, which happens
+    /// when the locale is not installed on the system.
+    pub fn new(name: &str) -> Locale {
+        let name = CString::new(name).unwrap();
+        let loc =
+            unsafe { libc::newlocale(libc::LC_ALL_MASK, name.as_ptr(), std::ptr::null_mut()) };
+        assert!(!loc.is_null(), "newlocale() failed for {:?}", name);
+        Locale(loc)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Locale {
+    fn drop(&mut self) {
+        unsafe { libc::freelocale(self.0) };
+    }
+}
+
+// A `locale_t` is just a handle; once built it is never mutated, so it can
+// safely be handed to another thread.
+#[cfg(unix)]
+unsafe impl Send for Locale {}
+
+/// A thin wrapper around `libc::tm` with a hand-written [`Debug`]
+/// implementation, so callers can inspect a time without needing the
+/// `libc` crate's `extra-traits` feature.
+///
+/// `Tm` derefs to `libc::tm`, so the usual field access and any function
+/// taking `&libc::tm` keep working; it also implements `AsRef<libc::tm>`
+/// so it can be passed to generic functions such as [`strftime_tm`].
+pub struct Tm(pub libc::tm);
+
+impl Tm {
+    /// Get a `Tm` in local timezone; see [`get_local_tm_from_epoch`].
+    pub fn local(epoch: libc::time_t) -> Tm {
+        Tm(get_local_tm_from_epoch(epoch))
+    }
+
+    /// Get a `Tm` in GMT; see [`get_gmt_tm_from_epoch`].
+    pub fn gmt(epoch: libc::time_t) -> Tm {
+        Tm(get_gmt_tm_from_epoch(epoch))
+    }
+}
+
+impl std::ops::Deref for Tm {
+    type Target = libc::tm;
+
+    fn deref(&self) -> &libc::tm {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Tm {
+    fn deref_mut(&mut self) -> &mut libc::tm {
+        &mut self.0
+    }
+}
+
+impl AsRef<libc::tm> for Tm {
+    fn as_ref(&self) -> &libc::tm {
+        &self.0
+    }
+}
+
+impl From<libc::tm> for Tm {
+    fn from(tm: libc::tm) -> Tm {
+        Tm(tm)
+    }
+}
+
+impl std::fmt::Debug for Tm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tm")
+            .field("tm_sec", &self.0.tm_sec)
+            .field("tm_min", &self.0.tm_min)
+            .field("tm_hour", &self.0.tm_hour)
+            .field("tm_mday", &self.0.tm_mday)
+            .field("tm_mon", &self.0.tm_mon)
+            .field("tm_year", &self.0.tm_year)
+            .field("tm_wday", &self.0.tm_wday)
+            .field("tm_yday", &self.0.tm_yday)
+            .field("tm_isdst", &self.0.tm_isdst)
+            .finish()
     }
 }
 
@@ -70,24 +221,182 @@ pub fn get_gmt_tm_from_epoch(epoch: libc::time_t) -> libc::tm {
     }
 }
 
+/// Errors returned by the fallible functions of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// A string passed to the underlying C function contains an interior
+    /// NUL byte.
+    InvalidFormat,
+    /// `strptime()` could not match the input against the given format.
+    NoMatch,
+    /// The formatted output didn't fit even after growing the buffer up to
+    /// [`MAX_STRFTIME_BUFFER`].
+    OutputTooLarge,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidFormat => write!(f, "input or format string contains an interior NUL byte"),
+            Error::NoMatch => write!(f, "input did not match the given format"),
+            Error::OutputTooLarge => write!(
+                f,
+                "formatted output exceeded {} bytes",
+                MAX_STRFTIME_BUFFER
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Upper bound on how large the buffer-doubling retry loop in
+/// `try_strftime`/`try_strftime_l` will grow before giving up with
+/// [`Error::OutputTooLarge`] instead of looping (and allocating) forever.
+const MAX_STRFTIME_BUFFER: usize = 1 << 20;
+
+/// Grow a buffer from 100 bytes, doubling each time, calling `call` on it
+/// until it reports a non-zero length or a buffer of [`MAX_STRFTIME_BUFFER`]
+/// bytes has been tried. Shared by [`try_strftime`] and [`try_strftime_l`],
+/// which only differ in which raw C function they hand the buffer to.
+///
+/// `call` mirrors `strftime()`/`strftime_l()`'s own ambiguity: it returns 0
+/// both when the buffer was too small and when the format legitimately
+/// produces an empty string, so an empty format is special-cased here to
+/// return an empty string rather than [`Error::OutputTooLarge`].
+fn try_strftime_with<F>(format: &str, mut call: F) -> Result<String, Error>
+where
+    F: FnMut(&mut [u8]) -> usize,
+{
+    if format.is_empty() {
+        return Ok(String::new());
+    }
+    let mut size = 100;
+    loop {
+        let mut buf = vec![0_u8; size];
+        let l = call(&mut buf);
+        if l > 0 {
+            buf.truncate(l);
+            return Ok(String::from_utf8_lossy(&buf).to_string());
+        }
+        if size >= MAX_STRFTIME_BUFFER {
+            return Err(Error::OutputTooLarge);
+        }
+        size = (size * 2).min(MAX_STRFTIME_BUFFER);
+    }
+}
+
+/// Call strftime() using a tm struct provided in input, growing the buffer
+/// and retrying if the formatted output didn't fit.
+fn try_strftime(format: &str, tm: &libc::tm) -> Result<String, Error> {
+    let f = CString::new(format).map_err(|_| Error::InvalidFormat)?;
+    try_strftime_with(format, |buf| unsafe {
+        c::strftime(buf.as_mut_ptr() as _, buf.len(), f.as_ptr() as *const _, tm)
+    })
+}
+
 /// Call strftime() using a tm struct provided in input
 fn strftime(format: &str, tm: &libc::tm) -> String {
-    let f = CString::new(format).unwrap();
-    let buf = [0_u8; 100];
-    let l: usize = unsafe { c::strftime(buf.as_ptr() as _, buf.len(), f.as_ptr() as *const _, tm) };
-    std::string::String::from_utf8_lossy(&buf[..l]).to_string()
+    try_strftime(format, tm).unwrap()
+}
+
+/// Call strftime() using the local timezone, returning an error instead of
+/// silently truncating when the format doesn't fit or is invalid.
+pub fn try_strftime_local(format: &str, epoch: libc::time_t) -> Result<String, Error> {
+    let tm = get_local_tm_from_epoch(epoch);
+    try_strftime(format, &tm)
+}
+
+/// Call strftime() using GMT, returning an error instead of silently
+/// truncating when the format doesn't fit or is invalid.
+pub fn try_strftime_gmt(format: &str, epoch: libc::time_t) -> Result<String, Error> {
+    let tm = get_gmt_tm_from_epoch(epoch);
+    try_strftime(format, &tm)
 }
 
 /// Call strftime() using the local timezone and returns a String
 pub fn strftime_local(format: &str, epoch: libc::time_t) -> String {
-    let tm = get_local_tm_from_epoch(epoch);
-    strftime(format, &tm)
+    try_strftime_local(format, epoch).unwrap()
 }
 
 /// Call strftime() using GMT and returns a String
 pub fn strftime_gmt(format: &str, epoch: libc::time_t) -> String {
+    try_strftime_gmt(format, epoch).unwrap()
+}
+
+/// Call strftime() on an already-resolved tm struct, such as a [`Tm`]
+/// obtained from [`Tm::local`]/[`Tm::gmt`] or from [`strptime`].
+///
+/// This is a separate function rather than an `AsRef<libc::tm>` overload
+/// of [`strftime_local`]/[`strftime_gmt`]: those two take an epoch and
+/// resolve it themselves (`libc::time_t`, not a tm struct), so widening
+/// their existing parameter to `impl AsRef<libc::tm>` isn't possible
+/// without breaking every caller. `strftime_tm` covers the same need --
+/// formatting anything that can be borrowed as a `libc::tm`, including a
+/// `Tm` -- without taking on that break.
+pub fn strftime_tm<T: AsRef<libc::tm>>(format: &str, tm: &T) -> String {
+    strftime(format, tm.as_ref())
+}
+
+/// Call strftime_l() using a tm struct and an explicit locale, growing the
+/// buffer and retrying if the formatted output didn't fit.
+#[cfg(unix)]
+fn try_strftime_l(format: &str, tm: &libc::tm, locale: &Locale) -> Result<String, Error> {
+    let f = CString::new(format).map_err(|_| Error::InvalidFormat)?;
+    try_strftime_with(format, |buf| unsafe {
+        c::strftime_l(
+            buf.as_mut_ptr() as _,
+            buf.len(),
+            f.as_ptr() as *const _,
+            tm,
+            locale.0,
+        )
+    })
+}
+
+/// Call strftime_l() using the local timezone and an explicit locale,
+/// without touching the global `LC_ALL` / `setlocale()` state.
+#[cfg(unix)]
+pub fn strftime_local_l(format: &str, epoch: libc::time_t, locale: &Locale) -> String {
+    let tm = get_local_tm_from_epoch(epoch);
+    try_strftime_l(format, &tm, locale).unwrap()
+}
+
+/// Call strftime_l() using GMT and an explicit locale, without touching the
+/// global `LC_ALL` / `setlocale()` state.
+#[cfg(unix)]
+pub fn strftime_gmt_l(format: &str, epoch: libc::time_t, locale: &Locale) -> String {
     let tm = get_gmt_tm_from_epoch(epoch);
-    strftime(format, &tm)
+    try_strftime_l(format, &tm, locale).unwrap()
+}
+
+/// Not reentrant: temporarily overrides the global `TZ` environment
+/// variable for the duration of the call. Calls made through this crate's
+/// own API are serialized against each other via [`TZ_LOCK`], but that
+/// doesn't protect against other code in the process reading or writing
+/// `TZ` concurrently.
+///
+/// Call strftime() in the given timezone.
+///
+/// This overrides `TZ` and restores it afterward; see the caveat on
+/// [`TimeZone`] about why, and on [`convert_epoch_between_zones`] for the
+/// same pattern.
+pub fn strftime_in_zone(format: &str, epoch: libc::time_t, tz: &TimeZone) -> String {
+    let _guard = TZ_LOCK.lock().unwrap();
+    let previous_tz = std::env::var("TZ").ok();
+
+    std::env::set_var("TZ", &tz.0);
+    tzset();
+    let tm = get_local_tm_from_epoch(epoch);
+    let result = strftime(format, &tm);
+
+    match previous_tz {
+        Some(tz) => std::env::set_var("TZ", tz),
+        None => std::env::remove_var("TZ"),
+    }
+    tzset();
+
+    result
 }
 
 /// Call setlocale() which will initialize the locale based on the environment variables
@@ -112,6 +421,171 @@ pub fn epoch() -> libc::time_t {
     unsafe { c::time(std::ptr::null()) }
 }
 
+/// Retrieve the current time as (epoch seconds, nanoseconds within the
+/// second), using `clock_gettime(CLOCK_REALTIME, ...)` for the sub-second
+/// resolution that [`epoch`] (backed by `time()`) cannot provide.
+#[cfg(unix)]
+pub fn epoch_precise() -> (libc::time_t, i64) {
+    unsafe {
+        let mut ts: libc::timespec = mem::zeroed();
+        c::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
+        (ts.tv_sec, ts.tv_nsec as i64)
+    }
+}
+
+/// Retrieve the current time as (epoch seconds, nanoseconds within the
+/// second). Windows has no equivalent to `clock_gettime()` without an
+/// extra dependency, so the nanosecond component is always 0 here.
+#[cfg(windows)]
+pub fn epoch_precise() -> (libc::time_t, i64) {
+    (epoch(), 0)
+}
+
+/// Expand `%N` into the zero-padded nanosecond component, respecting `%%`
+/// escaping so a literal `%%N` in the input isn't mistaken for the `%N`
+/// token (a blind `str::replace` would also turn a stray `%` from an
+/// unrelated directive into a bogus `strftime()` field-width prefix).
+fn expand_nanos_token(format: &str, nanos: i64) -> String {
+    let padded = format!("{:09}", nanos);
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('%') => {
+                out.push_str("%%");
+                chars.next();
+            }
+            Some('N') => {
+                out.push_str(&padded);
+                chars.next();
+            }
+            Some(&other) => {
+                out.push('%');
+                out.push(other);
+                chars.next();
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Like [`strftime_local`], but first expands `%N` into the zero-padded
+/// nanosecond component before handing the rest of the format to
+/// strftime(), since glibc's strftime() has no format specifier for
+/// fractional seconds.
+pub fn strftime_local_nanos(format: &str, sec: libc::time_t, nanos: i64) -> String {
+    let format = expand_nanos_token(format, nanos);
+    strftime_local(&format, sec)
+}
+
+/// Like [`strftime_gmt`], but first expands `%N` into the zero-padded
+/// nanosecond component before handing the rest of the format to
+/// strftime(), since glibc's strftime() has no format specifier for
+/// fractional seconds.
+pub fn strftime_gmt_nanos(format: &str, sec: libc::time_t, nanos: i64) -> String {
+    let format = expand_nanos_token(format, nanos);
+    strftime_gmt(&format, sec)
+}
+
+/// Parse a string into a tm struct using strptime(), the inverse of
+/// [`strftime_local`]/[`strftime_gmt`].
+///
+/// Returns [`Error::NoMatch`] when the input doesn't match the format.
+#[cfg(unix)]
+pub fn strptime(input: &str, format: &str) -> Result<libc::tm, Error> {
+    let input = CString::new(input).map_err(|_| Error::InvalidFormat)?;
+    let format = CString::new(format).map_err(|_| Error::InvalidFormat)?;
+    unsafe {
+        let mut tm: libc::tm = mem::zeroed();
+        let end = c::strptime(input.as_ptr(), format.as_ptr(), &mut tm);
+        if end.is_null() {
+            return Err(Error::NoMatch);
+        }
+        Ok(tm)
+    }
+}
+
+/// Parse a string and convert it to epoch seconds, interpreting the parsed
+/// time in the local timezone.
+#[cfg(unix)]
+pub fn parse_local_to_epoch(input: &str, format: &str) -> Result<libc::time_t, Error> {
+    let tm = strptime(input, format)?;
+    Ok(mktime_local(&tm))
+}
+
+/// Parse a string and convert it to epoch seconds, interpreting the parsed
+/// time as UTC.
+#[cfg(unix)]
+pub fn parse_gmt_to_epoch(input: &str, format: &str) -> Result<libc::time_t, Error> {
+    let tm = strptime(input, format)?;
+    Ok(timegm(&tm))
+}
+
+/// Convert a tm struct back to epoch seconds, interpreting it in the
+/// current local timezone.
+///
+/// `tm_isdst` is reset to `-1` before calling `mktime()` so libc determines
+/// whether DST is in effect itself, rather than trusting whatever value the
+/// input `tm` happened to carry (which may have come from a different
+/// timezone, as in [`convert_epoch_between_zones`]).
+pub fn mktime_local(tm: &libc::tm) -> libc::time_t {
+    let mut tm = *tm;
+    tm.tm_isdst = -1;
+    unsafe { c::mktime(&mut tm) }
+}
+
+/// Convert a tm struct back to epoch seconds, interpreting it as UTC.
+#[cfg(unix)]
+pub fn timegm(tm: &libc::tm) -> libc::time_t {
+    let mut tm = *tm;
+    unsafe { c::timegm(&mut tm) }
+}
+
+/// Not reentrant: temporarily overrides the global `TZ` environment
+/// variable for the duration of the call. Calls made through this crate's
+/// own API are serialized against each other via [`TZ_LOCK`] (shared with
+/// [`strftime_in_zone`]), but that doesn't protect against other code in
+/// the process reading or writing `TZ` concurrently.
+///
+/// Given a wall-clock time expressed as an epoch in `from_tz`, return the
+/// epoch of that same wall-clock time in `to_tz`.
+///
+/// This is the "same wall-clock time, different zone" conversion: the epoch
+/// itself changes because the same wall-clock reading means a different
+/// instant depending on which zone's offset is applied. It works by
+/// pointing `TZ` at `from_tz` to decompose the epoch into a broken-down
+/// time, then re-pointing `TZ` at `to_tz` to re-compose it; the previous
+/// `TZ` value (or its absence) is restored afterward.
+pub fn convert_epoch_between_zones(
+    epoch: libc::time_t,
+    from_tz: &str,
+    to_tz: &str,
+) -> libc::time_t {
+    let _guard = TZ_LOCK.lock().unwrap();
+    let previous_tz = std::env::var("TZ").ok();
+
+    std::env::set_var("TZ", from_tz);
+    tzset();
+    let tm = get_local_tm_from_epoch(epoch);
+
+    std::env::set_var("TZ", to_tz);
+    tzset();
+    let result = mktime_local(&tm);
+
+    match previous_tz {
+        Some(tz) => std::env::set_var("TZ", tz),
+        None => std::env::remove_var("TZ"),
+    }
+    tzset();
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -119,6 +593,39 @@ mod tests {
 
     const EPOCH: libc::time_t = 1_565_151_596;
 
+    #[test]
+    #[cfg(unix)]
+    fn parse_local_to_epoch_round_trips_through_dst() {
+        // New York is on EDT (UTC-4) in July; a naive tm_isdst=0 carried
+        // into mktime() would shift this epoch by an hour (see the
+        // mktime_local() fix in convert_epoch_between_zones_across_a_dst_boundary).
+        env::set_var("TZ", "America/New_York");
+        tzset();
+
+        let epoch = parse_local_to_epoch("2023-07-15 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(strftime_gmt("%Y-%m-%d %H:%M:%S", epoch), "2023-07-15 16:00:00");
+    }
+
+    #[test]
+    fn try_strftime_gmt_gives_up_past_max_buffer_size() {
+        // A format whose expansion can never fit, however much the buffer
+        // is doubled, must return an error instead of growing forever.
+        let format = "%Y".repeat(2_000_000);
+        match try_strftime_gmt(&format, EPOCH) {
+            Err(Error::OutputTooLarge) => {}
+            other => panic!("expected Error::OutputTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn strftime_gmt_nanos_preserves_percent_percent_escapes() {
+        // A naive `str::replace("%N", ...)` would also consume the `N` in
+        // a literal `%%N`, corrupting the `%%` escape and handing glibc a
+        // bogus huge field-width directive.
+        let s = strftime_gmt_nanos("literal %%N then %N real", EPOCH, 123_456_789);
+        assert_eq!(s, "literal %N then 123456789 real");
+    }
+
     #[test]
     fn format_time_and_date_in_gmt_and_cest() {
         env::set_var("LC_ALL", "en_US.UTF-8");
@@ -156,6 +663,51 @@ mod tests {
         assert_eq!(local, "Mer  7 aoû 06:19:56 2019");
     }
 
+    #[test]
+    fn strftime_tm_formats_a_tm_wrapper_directly() {
+        let tm = Tm::gmt(EPOCH);
+        assert_eq!(tm.tm_year, 119);
+        assert_eq!(strftime_tm("%Y-%m-%d", &tm), "2019-08-07");
+    }
+
+    #[test]
+    fn strftime_gmt_l_formats_with_an_explicit_locale() {
+        // "C" is used instead of a language locale like fr_BE.UTF-8 so
+        // this test doesn't depend on locale data being installed on the
+        // machine running it.
+        let locale = Locale::new("C");
+        assert_eq!(
+            strftime_gmt_l("%c", EPOCH, &locale),
+            "Wed Aug  7 04:19:56 2019"
+        );
+    }
+
+    #[test]
+    fn strftime_in_zone_formats_without_touching_the_current_tz() {
+        env::set_var("TZ", "Europe/Brussels");
+        tzset();
+
+        let tz = TimeZone::new("America/New_York");
+        let in_ny = strftime_in_zone("%H:%M", EPOCH, &tz);
+        assert_eq!(in_ny, "00:19");
+
+        // TZ is restored to what it was before the call.
+        assert_eq!(strftime_local("%H:%M", EPOCH), "06:19");
+    }
+
+    #[test]
+    fn convert_epoch_between_zones_across_a_dst_boundary() {
+        // 2023-10-29 12:00 UTC: New York is still on EDT (UTC-4, so
+        // 08:00 local) but London has already left BST for GMT. Carrying
+        // New York's tm_isdst=1 into the mktime() done under TZ=Europe/London
+        // would shift the result by an extra hour.
+        let ny_epoch = 1_698_580_800;
+        let result =
+            convert_epoch_between_zones(ny_epoch, "America/New_York", "Europe/London");
+        assert_eq!(result, 1_698_566_400);
+        assert_eq!(strftime_gmt("%H:%M", result), "08:00");
+    }
+
     // NOTE: I have no idea how to change the timezone or the language on
     //       Windows. It's supposed to be with the global environment variable
     //       TZ but I couldn't make it working... well, at least it returns